@@ -0,0 +1,54 @@
+// Memory-mapped address space that a CPU talks to.
+//
+// Splitting this out from the CPU means the CPU doesn't need to know whether
+// a given address is backed by plain RAM, a PPU/APU register, or a mapper --
+// it just calls `read`/`write` and lets the bus decide how to route it, the
+// same way address decoding works on real NES hardware.
+pub trait Bus {
+  fn read(&mut self, addr: u16) -> u8;
+  fn write(&mut self, addr: u16, data: u8);
+
+  fn read_u16(&mut self, pos: u16) -> u16 {
+    // Little-Endian: low byte first, high byte second.
+    let lo = self.read(pos) as u16;
+    let hi = self.read(pos.wrapping_add(1)) as u16;
+    (hi << 8) | lo
+  }
+
+  fn write_u16(&mut self, pos: u16, data: u16) {
+    let hi = (data >> 8) as u8;
+    let lo = (data & 0xff) as u8;
+    self.write(pos, lo);
+    self.write(pos.wrapping_add(1), hi);
+  }
+}
+
+/// A `Bus` implementation that is just a flat 64 KiB array, with no
+/// memory-mapped I/O devices wired in. Useful as the default for plain CPU
+/// tests and for anything that doesn't yet care about PPU/APU/mapper
+/// address decoding.
+pub struct RAM {
+  memory: [u8; 0x10000],
+}
+
+impl RAM {
+  pub fn new() -> Self {
+    RAM { memory: [0; 0x10000] }
+  }
+}
+
+impl Default for RAM {
+  fn default() -> Self {
+    RAM::new()
+  }
+}
+
+impl Bus for RAM {
+  fn read(&mut self, addr: u16) -> u8 {
+    self.memory[addr as usize]
+  }
+
+  fn write(&mut self, addr: u16, data: u8) {
+    self.memory[addr as usize] = data;
+  }
+}
@@ -0,0 +1,288 @@
+use crate::cpu::AddressingMode;
+
+// The 6502's official instructions, independent of variant (NMOS vs CMOS).
+// `Instruction` names the operation; `OpCode` pairs a concrete opcode byte
+// with the addressing mode and encoded length it implies, so decode can
+// drive program-counter advancement from the table instead of each match
+// arm hand-incrementing it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub enum Instruction {
+  LDA, LDX, LDY, STA, STX, STY,
+  TAX, TAY, TXA, TYA, TSX, TXS,
+  ADC, SBC,
+  AND, ORA, EOR, BIT,
+  ASL, LSR, ROL, ROR,
+  INC, INX, INY, DEC, DEX, DEY,
+  CMP, CPX, CPY,
+  BCC, BCS, BEQ, BMI, BNE, BPL, BVC, BVS,
+  JMP, JSR, RTS, RTI,
+  CLC, CLD, CLI, CLV, SEC, SED, SEI,
+  PHA, PLA, PHP, PLP,
+  NOP, BRK,
+
+  // 65C02-only instructions -- see `CMOS_OPCODES`.
+  STZ, BRA, PHX, PHY, PLX, PLY, TRB, TSB,
+}
+
+/// Which physical 6502 the decode table should model. The NMOS and CMOS
+/// parts share almost all of their encoding, but the CMOS part fills in
+/// opcodes that were undefined (and often harmful to rely on) on NMOS
+/// with genuinely new instructions, and fixes the NMOS JMP-indirect page
+/// wrap bug.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variant {
+  Nmos6502,
+  Cmos65C02,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct OpCode {
+  pub code: u8,
+  pub instruction: Instruction,
+  pub mode: AddressingMode,
+  pub len: u8,
+}
+
+impl OpCode {
+  const fn new(code: u8, instruction: Instruction, mode: AddressingMode, len: u8) -> Self {
+    OpCode { code, instruction, mode, len }
+  }
+}
+
+use AddressingMode::*;
+use Instruction::*;
+
+// NMOS 6502 opcode table. Kept as a flat array (rather than a HashMap) so
+// `decode` can do a simple linear scan without pulling in an external
+// dependency for this small, fixed-size table.
+pub const NMOS_OPCODES: &[OpCode] = &[
+  OpCode::new(0xA9, LDA, Immediate, 2),
+  OpCode::new(0xA5, LDA, ZeroPage, 2),
+  OpCode::new(0xB5, LDA, ZeroPage_X, 2),
+  OpCode::new(0xAD, LDA, Absolute, 3),
+  OpCode::new(0xBD, LDA, Absolute_X, 3),
+  OpCode::new(0xB9, LDA, Absolute_Y, 3),
+  OpCode::new(0xA1, LDA, Indirect_X, 2),
+  OpCode::new(0xB1, LDA, Indirect_Y, 2),
+
+  OpCode::new(0xA2, LDX, Immediate, 2),
+  OpCode::new(0xA6, LDX, ZeroPage, 2),
+  OpCode::new(0xB6, LDX, ZeroPage_Y, 2),
+  OpCode::new(0xAE, LDX, Absolute, 3),
+  OpCode::new(0xBE, LDX, Absolute_Y, 3),
+
+  OpCode::new(0xA0, LDY, Immediate, 2),
+  OpCode::new(0xA4, LDY, ZeroPage, 2),
+  OpCode::new(0xB4, LDY, ZeroPage_X, 2),
+  OpCode::new(0xAC, LDY, Absolute, 3),
+  OpCode::new(0xBC, LDY, Absolute_X, 3),
+
+  OpCode::new(0x85, STA, ZeroPage, 2),
+  OpCode::new(0x95, STA, ZeroPage_X, 2),
+  OpCode::new(0x8D, STA, Absolute, 3),
+  OpCode::new(0x9D, STA, Absolute_X, 3),
+  OpCode::new(0x99, STA, Absolute_Y, 3),
+  OpCode::new(0x81, STA, Indirect_X, 2),
+  OpCode::new(0x91, STA, Indirect_Y, 2),
+
+  OpCode::new(0x86, STX, ZeroPage, 2),
+  OpCode::new(0x96, STX, ZeroPage_Y, 2),
+  OpCode::new(0x8E, STX, Absolute, 3),
+
+  OpCode::new(0x84, STY, ZeroPage, 2),
+  OpCode::new(0x94, STY, ZeroPage_X, 2),
+  OpCode::new(0x8C, STY, Absolute, 3),
+
+  OpCode::new(0xAA, TAX, NoneAddressing, 1),
+  OpCode::new(0xA8, TAY, NoneAddressing, 1),
+  OpCode::new(0x8A, TXA, NoneAddressing, 1),
+  OpCode::new(0x98, TYA, NoneAddressing, 1),
+  OpCode::new(0xBA, TSX, NoneAddressing, 1),
+  OpCode::new(0x9A, TXS, NoneAddressing, 1),
+
+  OpCode::new(0x69, ADC, Immediate, 2),
+  OpCode::new(0x65, ADC, ZeroPage, 2),
+  OpCode::new(0x75, ADC, ZeroPage_X, 2),
+  OpCode::new(0x6D, ADC, Absolute, 3),
+  OpCode::new(0x7D, ADC, Absolute_X, 3),
+  OpCode::new(0x79, ADC, Absolute_Y, 3),
+  OpCode::new(0x61, ADC, Indirect_X, 2),
+  OpCode::new(0x71, ADC, Indirect_Y, 2),
+
+  OpCode::new(0xE9, SBC, Immediate, 2),
+  OpCode::new(0xE5, SBC, ZeroPage, 2),
+  OpCode::new(0xF5, SBC, ZeroPage_X, 2),
+  OpCode::new(0xED, SBC, Absolute, 3),
+  OpCode::new(0xFD, SBC, Absolute_X, 3),
+  OpCode::new(0xF9, SBC, Absolute_Y, 3),
+  OpCode::new(0xE1, SBC, Indirect_X, 2),
+  OpCode::new(0xF1, SBC, Indirect_Y, 2),
+
+  OpCode::new(0x29, AND, Immediate, 2),
+  OpCode::new(0x25, AND, ZeroPage, 2),
+  OpCode::new(0x35, AND, ZeroPage_X, 2),
+  OpCode::new(0x2D, AND, Absolute, 3),
+  OpCode::new(0x3D, AND, Absolute_X, 3),
+  OpCode::new(0x39, AND, Absolute_Y, 3),
+  OpCode::new(0x21, AND, Indirect_X, 2),
+  OpCode::new(0x31, AND, Indirect_Y, 2),
+
+  OpCode::new(0x09, ORA, Immediate, 2),
+  OpCode::new(0x05, ORA, ZeroPage, 2),
+  OpCode::new(0x15, ORA, ZeroPage_X, 2),
+  OpCode::new(0x0D, ORA, Absolute, 3),
+  OpCode::new(0x1D, ORA, Absolute_X, 3),
+  OpCode::new(0x19, ORA, Absolute_Y, 3),
+  OpCode::new(0x01, ORA, Indirect_X, 2),
+  OpCode::new(0x11, ORA, Indirect_Y, 2),
+
+  OpCode::new(0x49, EOR, Immediate, 2),
+  OpCode::new(0x45, EOR, ZeroPage, 2),
+  OpCode::new(0x55, EOR, ZeroPage_X, 2),
+  OpCode::new(0x4D, EOR, Absolute, 3),
+  OpCode::new(0x5D, EOR, Absolute_X, 3),
+  OpCode::new(0x59, EOR, Absolute_Y, 3),
+  OpCode::new(0x41, EOR, Indirect_X, 2),
+  OpCode::new(0x51, EOR, Indirect_Y, 2),
+
+  OpCode::new(0x24, BIT, ZeroPage, 2),
+  OpCode::new(0x2C, BIT, Absolute, 3),
+
+  OpCode::new(0x0A, ASL, Accumulator, 1),
+  OpCode::new(0x06, ASL, ZeroPage, 2),
+  OpCode::new(0x16, ASL, ZeroPage_X, 2),
+  OpCode::new(0x0E, ASL, Absolute, 3),
+  OpCode::new(0x1E, ASL, Absolute_X, 3),
+
+  OpCode::new(0x4A, LSR, Accumulator, 1),
+  OpCode::new(0x46, LSR, ZeroPage, 2),
+  OpCode::new(0x56, LSR, ZeroPage_X, 2),
+  OpCode::new(0x4E, LSR, Absolute, 3),
+  OpCode::new(0x5E, LSR, Absolute_X, 3),
+
+  OpCode::new(0x2A, ROL, Accumulator, 1),
+  OpCode::new(0x26, ROL, ZeroPage, 2),
+  OpCode::new(0x36, ROL, ZeroPage_X, 2),
+  OpCode::new(0x2E, ROL, Absolute, 3),
+  OpCode::new(0x3E, ROL, Absolute_X, 3),
+
+  OpCode::new(0x6A, ROR, Accumulator, 1),
+  OpCode::new(0x66, ROR, ZeroPage, 2),
+  OpCode::new(0x76, ROR, ZeroPage_X, 2),
+  OpCode::new(0x6E, ROR, Absolute, 3),
+  OpCode::new(0x7E, ROR, Absolute_X, 3),
+
+  OpCode::new(0xE6, INC, ZeroPage, 2),
+  OpCode::new(0xF6, INC, ZeroPage_X, 2),
+  OpCode::new(0xEE, INC, Absolute, 3),
+  OpCode::new(0xFE, INC, Absolute_X, 3),
+  OpCode::new(0xE8, INX, NoneAddressing, 1),
+  OpCode::new(0xC8, INY, NoneAddressing, 1),
+
+  OpCode::new(0xC6, DEC, ZeroPage, 2),
+  OpCode::new(0xD6, DEC, ZeroPage_X, 2),
+  OpCode::new(0xCE, DEC, Absolute, 3),
+  OpCode::new(0xDE, DEC, Absolute_X, 3),
+  OpCode::new(0xCA, DEX, NoneAddressing, 1),
+  OpCode::new(0x88, DEY, NoneAddressing, 1),
+
+  OpCode::new(0xC9, CMP, Immediate, 2),
+  OpCode::new(0xC5, CMP, ZeroPage, 2),
+  OpCode::new(0xD5, CMP, ZeroPage_X, 2),
+  OpCode::new(0xCD, CMP, Absolute, 3),
+  OpCode::new(0xDD, CMP, Absolute_X, 3),
+  OpCode::new(0xD9, CMP, Absolute_Y, 3),
+  OpCode::new(0xC1, CMP, Indirect_X, 2),
+  OpCode::new(0xD1, CMP, Indirect_Y, 2),
+
+  OpCode::new(0xE0, CPX, Immediate, 2),
+  OpCode::new(0xE4, CPX, ZeroPage, 2),
+  OpCode::new(0xEC, CPX, Absolute, 3),
+
+  OpCode::new(0xC0, CPY, Immediate, 2),
+  OpCode::new(0xC4, CPY, ZeroPage, 2),
+  OpCode::new(0xCC, CPY, Absolute, 3),
+
+  OpCode::new(0x90, BCC, Relative, 2),
+  OpCode::new(0xB0, BCS, Relative, 2),
+  OpCode::new(0xF0, BEQ, Relative, 2),
+  OpCode::new(0x30, BMI, Relative, 2),
+  OpCode::new(0xD0, BNE, Relative, 2),
+  OpCode::new(0x10, BPL, Relative, 2),
+  OpCode::new(0x50, BVC, Relative, 2),
+  OpCode::new(0x70, BVS, Relative, 2),
+
+  OpCode::new(0x4C, JMP, Absolute, 3),
+  OpCode::new(0x6C, JMP, Indirect, 3),
+  OpCode::new(0x20, JSR, Absolute, 3),
+  OpCode::new(0x60, RTS, NoneAddressing, 1),
+  OpCode::new(0x40, RTI, NoneAddressing, 1),
+
+  OpCode::new(0x18, CLC, NoneAddressing, 1),
+  OpCode::new(0xD8, CLD, NoneAddressing, 1),
+  OpCode::new(0x58, CLI, NoneAddressing, 1),
+  OpCode::new(0xB8, CLV, NoneAddressing, 1),
+  OpCode::new(0x38, SEC, NoneAddressing, 1),
+  OpCode::new(0xF8, SED, NoneAddressing, 1),
+  OpCode::new(0x78, SEI, NoneAddressing, 1),
+
+  OpCode::new(0x48, PHA, NoneAddressing, 1),
+  OpCode::new(0x68, PLA, NoneAddressing, 1),
+  OpCode::new(0x08, PHP, NoneAddressing, 1),
+  OpCode::new(0x28, PLP, NoneAddressing, 1),
+
+  OpCode::new(0xEA, NOP, NoneAddressing, 1),
+  OpCode::new(0x00, BRK, NoneAddressing, 1),
+];
+
+// Opcodes that only exist on the CMOS 65C02 -- either genuinely new
+// instructions, or new addressing-mode variants of existing ones (INC A,
+// DEC A, immediate BIT, and the zero-page-indirect forms of the
+// accumulator ops).
+pub const CMOS_OPCODES: &[OpCode] = &[
+  OpCode::new(0x64, STZ, ZeroPage, 2),
+  OpCode::new(0x74, STZ, ZeroPage_X, 2),
+  OpCode::new(0x9C, STZ, Absolute, 3),
+  OpCode::new(0x9E, STZ, Absolute_X, 3),
+
+  OpCode::new(0x80, BRA, Relative, 2),
+
+  OpCode::new(0xDA, PHX, NoneAddressing, 1),
+  OpCode::new(0x5A, PHY, NoneAddressing, 1),
+  OpCode::new(0xFA, PLX, NoneAddressing, 1),
+  OpCode::new(0x7A, PLY, NoneAddressing, 1),
+
+  OpCode::new(0x14, TRB, ZeroPage, 2),
+  OpCode::new(0x1C, TRB, Absolute, 3),
+  OpCode::new(0x04, TSB, ZeroPage, 2),
+  OpCode::new(0x0C, TSB, Absolute, 3),
+
+  OpCode::new(0x1A, INC, Accumulator, 1),
+  OpCode::new(0x3A, DEC, Accumulator, 1),
+
+  OpCode::new(0x89, BIT, Immediate, 2),
+
+  OpCode::new(0x12, ORA, ZeroPage_Indirect, 2),
+  OpCode::new(0x32, AND, ZeroPage_Indirect, 2),
+  OpCode::new(0x52, EOR, ZeroPage_Indirect, 2),
+  OpCode::new(0x72, ADC, ZeroPage_Indirect, 2),
+  OpCode::new(0x92, STA, ZeroPage_Indirect, 2),
+  OpCode::new(0xB2, LDA, ZeroPage_Indirect, 2),
+  OpCode::new(0xD2, CMP, ZeroPage_Indirect, 2),
+  OpCode::new(0xF2, SBC, ZeroPage_Indirect, 2),
+];
+
+/// Looks up the decoded form of an opcode byte for the given variant. CMOS
+/// opcodes are checked first since the 65C02 repurposes several bytes that
+/// are undefined on NMOS; anything it doesn't define falls back to the
+/// shared table.
+pub fn decode(code: u8, variant: Variant) -> Option<&'static OpCode> {
+  if variant == Variant::Cmos65C02 {
+    if let Some(op) = CMOS_OPCODES.iter().find(|op| op.code == code) {
+      return Some(op);
+    }
+  }
+
+  NMOS_OPCODES.iter().find(|op| op.code == code)
+}
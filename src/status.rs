@@ -0,0 +1,75 @@
+// Named bits of the 6502 processor status (`P`) register, so flag logic
+// reads as intent ("carry", "zero") instead of scattered hex literals. The
+// live register is still exposed externally as a plain `u8` (see
+// `CPU::status`) -- this type exists to make updates to it typed and
+// self-documenting, not to change what callers see.
+// https://www.nesdev.org/obelisk-6502-guide/registers.html#P
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Status(u8);
+
+impl Status {
+  pub const CARRY: u8 = 0b0000_0001;
+  pub const ZERO: u8 = 0b0000_0010;
+  pub const INTERRUPT_DISABLE: u8 = 0b0000_0100;
+  pub const DECIMAL: u8 = 0b0000_1000;
+  pub const BREAK: u8 = 0b0001_0000;
+  pub const UNUSED: u8 = 0b0010_0000;
+  pub const OVERFLOW: u8 = 0b0100_0000;
+  pub const NEGATIVE: u8 = 0b1000_0000;
+
+  pub fn contains(self, flag: u8) -> bool {
+    self.0 & flag != 0
+  }
+
+  pub fn set(&mut self, flag: u8, value: bool) {
+    if value {
+      self.0 |= flag;
+    } else {
+      self.0 &= !flag;
+    }
+  }
+
+  /// Sets ZERO from `result == 0` and NEGATIVE from bit 7 of `result`, the
+  /// pair nearly every load/transfer/arithmetic instruction updates together.
+  pub fn set_zero_and_negative(&mut self, result: u8) {
+    self.set(Self::ZERO, result == 0);
+    self.set(Self::NEGATIVE, result & Self::NEGATIVE != 0);
+  }
+
+  pub fn set_carry(&mut self, value: bool) {
+    self.set(Self::CARRY, value);
+  }
+
+  /// The byte actually pushed to the stack by PHP/BRK/IRQ/NMI: the unused
+  /// bit is always forced on, and the break bit reflects whether this is a
+  /// software break (BRK/PHP, `break_flag = true`) or a hardware interrupt
+  /// (IRQ/NMI, `break_flag = false`).
+  pub fn pushed_byte(self, break_flag: bool) -> u8 {
+    let mut flags = self;
+    flags.set(Self::BREAK, break_flag);
+    flags.set(Self::UNUSED, true);
+    flags.0
+  }
+
+  /// What PLP/RTI actually load back into the live status register: the
+  /// break bit never lives in the register itself (only on the stack copy),
+  /// and the unused bit always reads back as set.
+  pub fn pulled(self) -> Status {
+    let mut flags = self;
+    flags.set(Self::BREAK, false);
+    flags.set(Self::UNUSED, true);
+    flags
+  }
+}
+
+impl From<u8> for Status {
+  fn from(value: u8) -> Self {
+    Status(value)
+  }
+}
+
+impl From<Status> for u8 {
+  fn from(value: Status) -> Self {
+    value.0
+  }
+}